@@ -5,15 +5,21 @@ use anyhow_http::{
     ResultExt,
 };
 use axum::{
-    extract::{DefaultBodyLimit, Multipart, State},
+    extract::{
+        ws::{Message, WebSocket},
+        DefaultBodyLimit, Multipart, State, WebSocketUpgrade,
+    },
+    http::{HeaderMap, HeaderName},
     response::{IntoResponse, Response},
     routing::{get, post},
     Router,
 };
 use axum_server::Server;
+use bytes::Bytes;
 use c2pa::{Builder, CallbackSigner};
 use c2pa_crypto::raw_signature::SigningAlg;
-use futures::{io::AllowStdIo, AsyncWrite, TryStreamExt};
+use crc32fast;
+use futures::{io::AllowStdIo, stream, AsyncWrite, TryStreamExt};
 use hex;
 use http_body_util::StreamBody;
 use hyper::StatusCode;
@@ -25,20 +31,25 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::{
     collections::HashMap,
-    io::{self, Cursor, Seek, Write},
+    io::{self, Cursor, Read, Seek, Write},
     net::SocketAddr,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
 };
 use tempfile::NamedTempFile;
 use tokio::fs as tokio_fs;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::watch;
+use tokio_stream::wrappers::ReceiverStream;
 use tokio_util::compat::FuturesAsyncWriteCompatExt;
 use tokio_util::io::{ReaderStream, StreamReader};
 use tower_http::{cors::CorsLayer, limit::RequestBodyLimitLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use uuid::Uuid;
 
+mod store;
+use store::Store;
+
 const PYTHON_BACKEND_URL: &str = "http://python-backend:8001/process_video";
 const PYTHON_ANALYZE_URL: &str = "http://python-backend:8001/analyze_video";
 const PRIVATE_KEY: &[u8] = include_bytes!("../certs/ed25519.pem");
@@ -46,13 +57,99 @@ const PUBLIC_KEY: &[u8] = include_bytes!("../certs/ed25519.pub");
 
 #[derive(Debug, Clone)]
 struct AppState {
-    processing_status: Arc<Mutex<HashMap<String, ProcessingStatus>>>,
+    processing_status: Arc<Mutex<StatusChannels>>,
+    store: Arc<dyn Store>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 struct ProcessingStatus {
     status: String,
     error: Option<String>,
+    /// Set on a `"failed"` status caused by bad input (e.g. an unsupported/corrupt upload) rather
+    /// than an internal failure, so [`download_file`] can report it as a `400` instead of a `500`.
+    #[serde(default)]
+    client_error: bool,
+}
+
+impl ProcessingStatus {
+    fn uploading() -> Self {
+        Self {
+            status: "uploading".to_string(),
+            error: None,
+            client_error: false,
+        }
+    }
+
+    fn processing() -> Self {
+        Self {
+            status: "processing".to_string(),
+            error: None,
+            client_error: false,
+        }
+    }
+
+    fn completed() -> Self {
+        Self {
+            status: "completed".to_string(),
+            error: None,
+            client_error: false,
+        }
+    }
+
+    /// An internal failure — surfaced to the client as a `500`.
+    fn failed(error: impl Into<String>) -> Self {
+        Self {
+            status: "failed".to_string(),
+            error: Some(error.into()),
+            client_error: false,
+        }
+    }
+
+    /// A failure caused by the input itself (unsupported/corrupt video) — surfaced to the client
+    /// as a `400`.
+    fn client_failed(error: impl Into<String>) -> Self {
+        Self {
+            status: "failed".to_string(),
+            error: Some(error.into()),
+            client_error: true,
+        }
+    }
+
+    fn not_found() -> Self {
+        Self {
+            status: "not_found".to_string(),
+            error: Some("Session not found".to_string()),
+            client_error: false,
+        }
+    }
+}
+
+/// One [`watch`] channel per in-flight/completed session, so both polling (`/status/{id}`) and
+/// the WebSocket push (`/ws/status/{id}`) read from the same source of truth, and a connected
+/// socket is notified the instant [`process_video`] changes a session's status.
+type StatusChannels = HashMap<String, watch::Sender<ProcessingStatus>>;
+
+/// Starts tracking a new session at `initial`, overwriting any previous channel for the id.
+fn start_status(status: &Mutex<StatusChannels>, session_id: &str, initial: ProcessingStatus) {
+    let (tx, _rx) = watch::channel(initial);
+    status.lock().unwrap().insert(session_id.to_string(), tx);
+}
+
+/// Publishes a status transition for an already-tracked session. A no-op if the session isn't
+/// tracked (e.g. it was never started); unlike a plain map insert, it never adds a new session.
+fn publish_status(status: &Mutex<StatusChannels>, session_id: &str, new_status: ProcessingStatus) {
+    if let Some(tx) = status.lock().unwrap().get(session_id) {
+        let _ = tx.send(new_status);
+    }
+}
+
+/// Reads the latest status for `session_id`, if the session is tracked.
+fn current_status(status: &Mutex<StatusChannels>, session_id: &str) -> Option<ProcessingStatus> {
+    status
+        .lock()
+        .unwrap()
+        .get(session_id)
+        .map(|tx| tx.borrow().clone())
 }
 
 #[derive(Debug, Deserialize)]
@@ -62,13 +159,21 @@ struct PythonAnalyzeResponse {
 
 #[derive(Debug, Clone, Serialize, serde::Deserialize)]
 struct VideoData {
+    /// Opaque identifier of the signed video in the configured [`Store`], not a filesystem path.
     path: String,
     message_bits: Vec<f32>,
 }
 
-static VIDEO_STORE: Lazy<Mutex<HashMap<String, VideoData>>> =
+/// Keyed by the 64-bit payload id encoded into the watermark (see [`encode_payload`]), so a
+/// successfully decoded extraction maps straight to its video without scanning the whole store.
+static VIDEO_STORE: Lazy<Mutex<HashMap<u64, VideoData>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
+/// Id the `u64 → VideoData` index is persisted under in the configured [`Store`], alongside the
+/// signed videos it maps to, so the index survives container loss and is shared across replicas
+/// the same way the videos themselves already are.
+const VIDEO_STORE_OBJECT_ID: &str = "video_store.json";
+
 fn message_bits_to_hex(message_bits: &[f32]) -> String {
     // Convert f32 bits to actual bits (0 or 1)
     let bits: Vec<u8> = message_bits
@@ -97,21 +202,141 @@ fn message_bits_to_hex(message_bits: &[f32]) -> String {
     hex::encode(bytes)
 }
 
+/// Inverse of [`message_bits_to_hex`]: unpacks the bit-packed hex string back into soft-bit
+/// values (1.0/0.0), MSB-first per byte, matching the packing order `message_bits_to_hex` used.
+/// Packing pads the final byte with zero bits, so callers must truncate the result to the
+/// original bit count (see `message_bits_len` in the watermark assertion).
 fn message_bits_from_hex(hex: &str) -> Vec<f32> {
-    let mut bits = Vec::new();
-    for chunk in hex.as_bytes().chunks(8) {
-        if let Ok(bytes) = std::str::from_utf8(chunk) {
-            if let Ok(decoded) = hex::decode(bytes) {
-                if let Ok(arr) = decoded.try_into() {
-                    bits.push(f32::from_be_bytes(arr));
-                }
-            }
-        }
+    let Ok(bytes) = hex::decode(hex) else {
+        return Vec::new();
+    };
+    bytes
+        .iter()
+        .flat_map(|&byte| (0..8).rev().map(move |i| if (byte >> i) & 1 == 1 { 1.0 } else { 0.0 }))
+        .collect()
+}
+
+/// Bits of the fixed-width message id (a truncated UUID) carried by every watermark.
+const PAYLOAD_ID_BITS: u32 = 64;
+/// Bits of CRC-32 appended after the id, checked on decode before trusting a recovered id.
+const PAYLOAD_CRC_BITS: u32 = 32;
+/// Total uncoded payload bits (id + CRC) before repetition coding.
+const PAYLOAD_BITS: u32 = PAYLOAD_ID_BITS + PAYLOAD_CRC_BITS;
+
+/// Default total soft-bit budget accepted by the embedder, used to size the repetition factor
+/// when `WATERMARK_BIT_BUDGET` isn't set.
+const DEFAULT_WATERMARK_BIT_BUDGET: usize = 864;
+
+fn watermark_bit_budget() -> usize {
+    std::env::var("WATERMARK_BIT_BUDGET")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_WATERMARK_BIT_BUDGET)
+}
+
+/// How many times each payload bit is repeated so [`decode_payload`] can recover it from noisy
+/// extracted soft-bits via majority vote. Derived from [`watermark_bit_budget`] so the coded
+/// payload fills the embedder's bit budget; overridable directly via `WATERMARK_REPETITION_FACTOR`.
+fn repetition_factor() -> usize {
+    std::env::var("WATERMARK_REPETITION_FACTOR")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_else(|| (watermark_bit_budget() / PAYLOAD_BITS as usize).max(1))
+}
+
+/// Default normalized Hamming distance (mismatched bits / total bits) above which
+/// [`find_closest_video`] rejects a nearest-neighbor fallback match rather than return it.
+const DEFAULT_MAX_WATERMARK_HAMMING_DISTANCE: f32 = 0.15;
+
+fn max_watermark_hamming_distance() -> f32 {
+    std::env::var("WATERMARK_MAX_HAMMING_DISTANCE")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_MAX_WATERMARK_HAMMING_DISTANCE)
+}
+
+/// Encodes `id` as a CRC-32-protected, repetition-coded soft-bit vector ready for the embedder.
+///
+/// The id (MSB first) followed by a CRC-32 of its big-endian bytes is repeated `repetition`
+/// times per bit, so [`decode_payload`] can recover it from noisy extracted bits by majority vote.
+fn encode_payload(id: u64, repetition: usize) -> Vec<f32> {
+    let crc = crc32fast::hash(&id.to_be_bytes());
+    let bits = (0..PAYLOAD_ID_BITS)
+        .rev()
+        .map(|i| ((id >> i) & 1) as u8)
+        .chain((0..PAYLOAD_CRC_BITS).rev().map(|i| ((crc >> i) & 1) as u8));
+
+    bits.flat_map(|bit| std::iter::repeat(bit as f32).take(repetition))
+        .collect()
+}
+
+/// A payload id recovered from noisy extracted bits, along with how noisy the extraction was.
+#[derive(Debug, Clone, Serialize)]
+struct DecodedPayload {
+    id: u64,
+    /// Fraction of repeated soft-bits that disagreed with the majority decision for their group,
+    /// averaged over the whole payload.
+    error_rate: f32,
+}
+
+/// Majority-vote decodes a payload encoded by [`encode_payload`] out of noisy extracted soft-bits.
+///
+/// Returns `None` if `extracted_bits` doesn't contain a full coded payload for `repetition`, or if
+/// the decoded CRC doesn't match the decoded id (the repetition coding wasn't enough to recover a
+/// clean payload, e.g. the watermark wasn't present or was damaged).
+fn decode_payload(extracted_bits: &[f32], repetition: usize) -> Option<DecodedPayload> {
+    let coded_len = PAYLOAD_BITS as usize * repetition;
+    if repetition == 0 || extracted_bits.len() < coded_len {
+        return None;
+    }
+
+    let mut bits = Vec::with_capacity(PAYLOAD_BITS as usize);
+    let mut disagreements = 0usize;
+    for group in extracted_bits[..coded_len].chunks(repetition) {
+        let votes = group.iter().filter(|&&v| v > 0.5).count();
+        let bit = (votes * 2 >= group.len()) as u8;
+        disagreements += group.iter().filter(|&&v| (v > 0.5) as u8 != bit).count();
+        bits.push(bit);
+    }
+    let error_rate = disagreements as f32 / coded_len as f32;
+
+    let id = bits[..PAYLOAD_ID_BITS as usize]
+        .iter()
+        .fold(0u64, |acc, &bit| (acc << 1) | bit as u64);
+    let crc = bits[PAYLOAD_ID_BITS as usize..]
+        .iter()
+        .fold(0u32, |acc, &bit| (acc << 1) | bit as u32);
+
+    if crc != crc32fast::hash(&id.to_be_bytes()) {
+        return None;
+    }
+
+    Some(DecodedPayload { id, error_rate })
+}
+
+/// Normalized Hamming distance (mismatched hard bits / compared bits) between two soft-bit
+/// vectors, used by [`find_closest_video`]'s nearest-neighbor fallback.
+fn normalized_hamming_distance(a: &[f32], b: &[f32]) -> f32 {
+    let len = a.len().min(b.len());
+    if len == 0 {
+        return 1.0;
     }
-    bits
+    let mismatches = a
+        .iter()
+        .zip(b.iter())
+        .take(len)
+        .filter(|(&x, &y)| (x > 0.5) != (y > 0.5))
+        .count();
+    mismatches as f32 / len as f32
 }
 
-fn manifest_def(title: &str, format: &str, message_bits: &[f32]) -> String {
+fn manifest_def(
+    title: &str,
+    format: &str,
+    message_bits: &[f32],
+    repetition_factor: usize,
+    payload_bits: u32,
+) -> String {
     json!({
         "title": title,
         "format": format,
@@ -124,6 +349,10 @@ fn manifest_def(title: &str, format: &str, message_bits: &[f32]) -> String {
             "data": {
                 "message_bits_hex": message_bits_to_hex(message_bits),
                 "message_bits_len": message_bits.len(),  // Store original length to handle padding
+                // Recorded so a decoder can recover the payload id without knowing this
+                // service's current configuration.
+                "repetition_factor": repetition_factor,
+                "payload_bits": payload_bits,
                 "action": "c2pa.watermarked",
                 "softwareAgent": {
                     "name": "C2PA Watermarking Service",
@@ -135,14 +364,111 @@ fn manifest_def(title: &str, format: &str, message_bits: &[f32]) -> String {
     .to_string()
 }
 
+/// Default cap, in bytes, on how much of a video [`VideoBuffer`] keeps in memory before
+/// spilling to a temp file under `./data/temp`. Overridable via `IN_MEMORY_VIDEO_THRESHOLD_BYTES`.
+const DEFAULT_IN_MEMORY_VIDEO_THRESHOLD_BYTES: u64 = 64 * 1024 * 1024;
+
+fn in_memory_video_threshold_bytes() -> u64 {
+    std::env::var("IN_MEMORY_VIDEO_THRESHOLD_BYTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_IN_MEMORY_VIDEO_THRESHOLD_BYTES)
+}
+
+/// A `Read + Write + Seek` buffer used as the source/destination for C2PA signing.
+///
+/// Starts in memory and transparently spills to a `NamedTempFile` under `./data/temp` the first
+/// time a write would push it past `max_in_memory_bytes`, so small/typical videos never touch
+/// disk while very large ones don't need to fit in RAM. The temp file is unlinked as soon as the
+/// in-memory contents have been copied into it (its contents stay reachable through the open
+/// handle), so no files are left behind in `./data/temp` even in the spill case.
+enum VideoBuffer {
+    Memory { data: Cursor<Vec<u8>>, max_in_memory_bytes: u64 },
+    Disk(std::fs::File),
+}
+
+impl VideoBuffer {
+    fn new(max_in_memory_bytes: u64) -> Self {
+        VideoBuffer::Memory {
+            data: Cursor::new(Vec::new()),
+            max_in_memory_bytes,
+        }
+    }
+
+    fn spill_to_disk(data: &Cursor<Vec<u8>>) -> io::Result<std::fs::File> {
+        let mut temp = NamedTempFile::new_in("./data/temp")?;
+        temp.write_all(data.get_ref())?;
+        temp.as_file_mut().seek(io::SeekFrom::Start(data.position()))?;
+        Ok(temp.into_file())
+    }
+
+    /// Converts the buffer into a [`store::ByteStream`] suitable for [`Store::save_stream`].
+    fn into_byte_stream(self) -> store::ByteStream {
+        match self {
+            VideoBuffer::Memory { data, .. } => {
+                let bytes = Bytes::from(data.into_inner());
+                Box::pin(stream::once(async move { Ok::<_, io::Error>(bytes) }))
+            }
+            VideoBuffer::Disk(file) => {
+                Box::pin(ReaderStream::new(tokio::fs::File::from_std(file)))
+            }
+        }
+    }
+}
+
+impl Write for VideoBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let VideoBuffer::Memory {
+            data,
+            max_in_memory_bytes,
+        } = self
+        {
+            if data.get_ref().len() as u64 + buf.len() as u64 > *max_in_memory_bytes {
+                *self = VideoBuffer::Disk(Self::spill_to_disk(data)?);
+            }
+        }
+        match self {
+            VideoBuffer::Memory { data, .. } => data.write(buf),
+            VideoBuffer::Disk(file) => file.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            VideoBuffer::Memory { data, .. } => data.flush(),
+            VideoBuffer::Disk(file) => file.flush(),
+        }
+    }
+}
+
+impl Read for VideoBuffer {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            VideoBuffer::Memory { data, .. } => data.read(buf),
+            VideoBuffer::Disk(file) => file.read(buf),
+        }
+    }
+}
+
+impl Seek for VideoBuffer {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        match self {
+            VideoBuffer::Memory { data, .. } => data.seek(pos),
+            VideoBuffer::Disk(file) => file.seek(pos),
+        }
+    }
+}
+
 async fn create_c2pa_manifest(
-    mut video: NamedTempFile,
+    mut video: VideoBuffer,
     message_bits: Vec<f32>,
-) -> Result<NamedTempFile> {
-    // Create manifest definition
+    repetition_factor: usize,
+) -> Result<VideoBuffer> {
+    // Create manifest definition. `normalize_video_format` guarantees every video reaching here
+    // is normalized MP4/H.264, so the format is always this constant, not detected per-upload.
     let format = "video/mp4";
     let title = "watermarked_video.mp4";
-    let json = manifest_def(title, format, &message_bits);
+    let json = manifest_def(title, format, &message_bits, repetition_factor, PAYLOAD_BITS);
 
     // Create builder from JSON
     let mut builder = Builder::from_json(&json)?;
@@ -161,30 +487,199 @@ async fn create_c2pa_manifest(
 
     // Create builder from archive and sign
     let mut builder = Builder::from_archive(&mut zipped)?;
-    let mut dest = NamedTempFile::new_in("./data/temp")?;
-    builder.sign(&signer, format, video.as_file_mut(), dest.as_file_mut())?;
+    video.rewind()?;
+    let mut dest = VideoBuffer::new(in_memory_video_threshold_bytes());
+    builder.sign(&signer, format, &mut video, &mut dest)?;
     dest.flush()?;
+    dest.rewind()?;
 
     Ok(dest)
 }
 
+/// Why [`normalize_video_format`] couldn't produce a video for the embedder. Kept distinct from
+/// the generic `anyhow::Error` used elsewhere in the pipeline so handlers can surface it as a
+/// `400` instead of failing deep inside C2PA signing.
+#[derive(Debug)]
+enum VideoFormatError {
+    /// `ffprobe` couldn't make sense of the input at all.
+    Corrupt(String),
+    /// `ffprobe` succeeded but `ffmpeg` could not transcode the detected codec/container.
+    Unsupported(String),
+}
+
+impl std::fmt::Display for VideoFormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VideoFormatError::Corrupt(msg) => write!(f, "corrupt or unreadable video: {}", msg),
+            VideoFormatError::Unsupported(msg) => write!(f, "unsupported video format: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for VideoFormatError {}
+
+/// Container format and video codec `ffprobe` detected for the file at `path`.
+async fn probe_container_format(path: &Path) -> Result<(String, Option<String>), VideoFormatError> {
+    let output = tokio::process::Command::new("ffprobe")
+        .arg("-v")
+        .arg("error")
+        .arg("-print_format")
+        .arg("json")
+        .arg("-show_entries")
+        .arg("format=format_name:stream=codec_type,codec_name")
+        .arg(path)
+        .output()
+        .await
+        .map_err(|e| VideoFormatError::Corrupt(format!("failed to run ffprobe: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(VideoFormatError::Corrupt(format!(
+            "ffprobe rejected the input: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| VideoFormatError::Corrupt(format!("failed to parse ffprobe output: {}", e)))?;
+
+    let format_name = parsed["format"]["format_name"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    if format_name.is_empty() {
+        return Err(VideoFormatError::Corrupt(
+            "ffprobe found no container format".to_string(),
+        ));
+    }
+
+    let video_codec = parsed["streams"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|stream| stream["codec_type"].as_str() == Some("video"))
+        .and_then(|stream| stream["codec_name"].as_str())
+        .map(str::to_string);
+
+    Ok((format_name, video_codec))
+}
+
+/// Whether the probed container/codec already satisfy the embedder, so no transcode is needed.
+fn is_normalized_mp4(format_name: &str, video_codec: Option<&str>) -> bool {
+    format_name.split(',').any(|name| name == "mp4") && video_codec.as_deref() == Some("h264")
+}
+
+/// Transcodes `input` to H.264-in-MP4 at `output`.
+async fn transcode_to_mp4(input: &Path, output: &Path) -> Result<(), VideoFormatError> {
+    let result = tokio::process::Command::new("ffmpeg")
+        .arg("-y")
+        .arg("-i")
+        .arg(input)
+        .arg("-c:v")
+        .arg("libx264")
+        .arg("-c:a")
+        .arg("aac")
+        .arg(output)
+        .output()
+        .await
+        .map_err(|e| VideoFormatError::Unsupported(format!("failed to run ffmpeg: {}", e)))?;
+
+    if !result.status.success() {
+        return Err(VideoFormatError::Unsupported(format!(
+            "ffmpeg failed to transcode: {}",
+            String::from_utf8_lossy(&result.stderr)
+        )));
+    }
+    Ok(())
+}
+
+/// Probes `input` and, if its container/codec don't already satisfy the embedder, transcodes it
+/// to normalized MP4/H.264 at `transcoded_path`. Returns the path to use downstream (`input`
+/// unchanged if it was already normalized). The embedder and every downstream consumer
+/// (`create_c2pa_manifest`, the Python backend, `/download`) only ever deal in MP4, since that's
+/// what this function normalizes every input to.
+async fn normalize_video_format(
+    input: &Path,
+    transcoded_path: &Path,
+) -> Result<PathBuf, VideoFormatError> {
+    let (format_name, video_codec) = probe_container_format(input).await?;
+    if is_normalized_mp4(&format_name, video_codec.as_deref()) {
+        return Ok(input.to_path_buf());
+    }
+    transcode_to_mp4(input, transcoded_path).await?;
+    Ok(transcoded_path.to_path_buf())
+}
+
+/// Best-effort removes the file at `0` when dropped, ignoring errors (e.g. it was never created,
+/// or another guard already removed it). Used so `process_video`'s scratch files under
+/// `./data/temp` are cleaned up on every exit path — success, `?`, or `bail!` — without having to
+/// repeat a removal call at each one.
+struct TempFileGuard(PathBuf);
+
+impl Drop for TempFileGuard {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
 async fn process_video(
-    input_path: PathBuf,
-    output_path: PathBuf,
-    status: Arc<Mutex<HashMap<String, ProcessingStatus>>>,
+    mut input_stream: store::ByteStream,
+    output_id: String,
+    blob_store: Arc<dyn Store>,
+    status: Arc<Mutex<StatusChannels>>,
     session_id: String,
 ) -> Result<()> {
     let client = reqwest::Client::new();
 
-    // Create multipart form for the video
-    let form = reqwest::multipart::Form::new().part(
-        "video",
-        reqwest::multipart::Part::file(&input_path)
-            .await
-            .unwrap()
+    // Every video gets a fresh 64-bit payload id, repetition-coded into the soft-bit vector the
+    // embedder is asked to watermark. Decoding later recovers this id directly instead of
+    // scanning the whole store for a nearest match.
+    let id = Uuid::new_v4().as_u128() as u64;
+    let repetition = repetition_factor();
+    let payload_bits = encode_payload(id, repetition);
+
+    // `ffprobe`/`ffmpeg` need a seekable file to inspect and, if necessary, transcode, so the
+    // incoming stream is materialized to disk here before anything else touches it. Both guards
+    // remove their file on drop however this function returns, including the early `bail!`s
+    // below and `?`-propagated errors further down.
+    let source_path = std::path::Path::new("./data/temp").join(format!("{}-source", session_id));
+    let transcoded_path =
+        std::path::Path::new("./data/temp").join(format!("{}-normalized.mp4", session_id));
+    let _source_guard = TempFileGuard(source_path.clone());
+    let _transcoded_guard = TempFileGuard(transcoded_path.clone());
+    {
+        let mut source_file = tokio_fs::File::create(&source_path).await?;
+        while let Some(chunk) = input_stream.try_next().await? {
+            source_file.write_all(&chunk).await?;
+        }
+        source_file.flush().await?;
+    }
+
+    let video_path = match normalize_video_format(&source_path, &transcoded_path).await {
+        Ok(path) => path,
+        Err(e) => {
+            let error_msg = e.to_string();
+            publish_status(
+                &status,
+                &session_id,
+                ProcessingStatus::client_failed(error_msg.clone()),
+            );
+            bail!(error_msg);
+        }
+    };
+
+    // Create multipart form, streaming the normalized video from its scratch file to the Python
+    // backend rather than reading it fully into memory.
+    let video_file = tokio_fs::File::open(&video_path).await?;
+    let form = reqwest::multipart::Form::new()
+        .part(
+            "video",
+            reqwest::multipart::Part::stream(reqwest::Body::wrap_stream(ReaderStream::new(
+                video_file,
+            )))
             .file_name("video.mp4")
             .mime_str("video/mp4")?,
-    );
+        )
+        .text("message_bits", serde_json::to_string(&payload_bits)?);
 
     // Send to Python backend
     let response = client
@@ -208,139 +703,103 @@ async fn process_video(
         // let boundary
         let mut multipart = multer::Multipart::new(response.bytes_stream(), boundary);
 
-        // Create temporary file for the watermarked video
-        let mut temp_file = NamedTempFile::new_in("./data/temp")?;
+        // Buffer the watermarked video in memory, spilling to disk only past the threshold.
+        let mut video_buffer = VideoBuffer::new(in_memory_video_threshold_bytes());
 
         let mut file_written = false;
-        let mut message_bits_received = false;
-        let mut message_bits: Vec<f32> = vec![];
         while let Some(mut field) = multipart.next_field().await? {
-            if let Some(name) = field.name() {
-                if name == "video" {
-                    let body_with_io_error =
-                        field.map_err(|err| io::Error::new(io::ErrorKind::Other, err));
-                    let body_reader = StreamReader::new(body_with_io_error);
-                    futures::pin_mut!(body_reader);
-                    tokio::io::copy(
-                        &mut body_reader,
-                        &mut AllowStdIo::new(temp_file.as_file_mut()).compat_write(),
-                    )
-                    .await?;
-                    file_written = true;
-                } else if name == "message_bits" {
-                    message_bits = field.json().await?;
-                    message_bits_received = true;
+            if field.name() == Some("video") {
+                while let Some(chunk) = field.chunk().await? {
+                    video_buffer.write_all(&chunk)?;
                 }
+                file_written = true;
             }
         }
         if !file_written {
             bail!("python response did not contain watermarked video");
         }
-        if !message_bits_received {
-            bail!("python response did not contain message bits");
-        }
 
-        // Create C2PA manifest for the video
-        let manifest_file = create_c2pa_manifest(temp_file, message_bits.clone())
+        // Create C2PA manifest for the video, recording the coded payload bits we asked the
+        // embedder to watermark (not an echo from the response, since the embedder only returns
+        // the video now).
+        let manifest_buffer = create_c2pa_manifest(video_buffer, payload_bits.clone(), repetition)
             .await
             .context("failed to create C2PA manifest")?;
 
-        // Read the signed video and save to output
-        manifest_file.persist(&output_path)?;
+        // Stream the signed video into the configured object store under its opaque id
+        blob_store
+            .save_stream(&output_id, manifest_buffer.into_byte_stream())
+            .await
+            .context("failed to save signed video to store")?;
 
-        // Store the video data in our global store
+        // Store the video data in our global store, keyed by the payload id so extraction can
+        // look it up directly instead of scanning for the closest match.
         {
             let mut store = VIDEO_STORE.lock().unwrap();
             store.insert(
-                message_bits_to_hex(&message_bits),
+                id,
                 VideoData {
-                    path: output_path.to_string_lossy().to_string(),
-                    message_bits,
+                    path: output_id.clone(),
+                    message_bits: payload_bits,
                 },
             );
         }
 
         // Save store after modification
-        if let Err(e) = save_video_store().await {
+        if let Err(e) = save_video_store(blob_store.as_ref()).await {
             eprintln!("Failed to save video store: {}", e);
         }
 
         // Update status
-        status.lock().unwrap().insert(
-            session_id,
-            ProcessingStatus {
-                status: "completed".to_string(),
-                error: None,
-            },
-        );
+        publish_status(&status, &session_id, ProcessingStatus::completed());
     } else {
         let error_msg = format!("Failed to process video: {}", response.status());
-        status.lock().unwrap().insert(
-            session_id,
-            ProcessingStatus {
-                status: "failed".to_string(),
-                error: Some(error_msg.clone()),
-            },
-        );
+        publish_status(&status, &session_id, ProcessingStatus::failed(error_msg.clone()));
         bail!(error_msg)
     }
 
     Ok(())
 }
 
+/// How many pending chunks [`upload_file`] buffers between reading the incoming multipart field
+/// and the background task forwarding them to the Python backend. Bounded so a slow downstream
+/// applies backpressure to the upload instead of buffering the whole video in memory.
+const UPLOAD_CHANNEL_CAPACITY: usize = 16;
+
 #[axum::debug_handler]
 async fn upload_file(
     State(state): State<AppState>,
     mut multipart: Multipart,
 ) -> HttpJsonResult<String> {
     let session_id = Uuid::new_v4().to_string();
-
-    let mut input_file = NamedTempFile::new_in("./data/temp").unwrap();
-    let output_path = PathBuf::from("./data/processed").join(format!("{}.mp4", session_id));
+    let output_id = format!("{}.mp4", session_id);
 
     // Initialize status
-    state.processing_status.lock().unwrap().insert(
-        session_id.clone(),
-        ProcessingStatus {
-            status: "uploading".to_string(),
-            error: None,
-        },
+    start_status(
+        &state.processing_status,
+        &session_id,
+        ProcessingStatus::uploading(),
     );
 
+    // Unlike `ingest_url`, the upload is streamed straight into `process_video` as it arrives
+    // (see the channel below) rather than materialized to disk first, so `normalize_video_format`
+    // can't be probed synchronously here before returning `session_id` without giving up that
+    // streaming. An unsupported/corrupt upload is instead reported through `ProcessingStatus`
+    // with `client_error: true`, which `download_file` turns into a `400`.
     while let Some(mut field) = multipart.next_field().await.unwrap() {
         if field.name().unwrap() == "video" {
-            loop {
-                let chunk_result = match field.chunk().await {
-                    Ok(maybe_chunk) => maybe_chunk,
-                    Err(e) => {
-                        eprintln!("Error getting chunk: {}", e);
-                        http_error_bail!(INTERNAL_SERVER_ERROR, "Failed to process upload: {}", e)
-                    }
-                };
-
-                // If no more chunks, break the loop
-                if chunk_result.is_none() {
-                    break;
-                }
-
-                // Unwrap is safe here because we checked is_none() above
-                let chunk = chunk_result.unwrap();
-
-                if let Err(e) = input_file.write_all(&chunk) {
-                    eprintln!("Error writing chunk to file: {}", e);
-                    http_error_bail!(INTERNAL_SERVER_ERROR, "failed to write file: {}", e)
-                }
-            }
-
             // Update status and start processing
-            state.processing_status.lock().unwrap().insert(
-                session_id.clone(),
-                ProcessingStatus {
-                    status: "processing".to_string(),
-                    error: None,
-                },
+            publish_status(
+                &state.processing_status,
+                &session_id,
+                ProcessingStatus::processing(),
             );
 
+            // Forward chunks to the background task through a bounded channel, so the incoming
+            // upload is handed to `process_video` as it arrives instead of being buffered here
+            // first; `process_video` writes it to a scratch file for probing before sending it on.
+            let (tx, rx) = tokio::sync::mpsc::channel::<io::Result<Bytes>>(UPLOAD_CHANNEL_CAPACITY);
+
             // Process the video in the background
             let state_clone = state.clone();
             let session_id_clone = session_id.clone();
@@ -348,73 +807,393 @@ async fn upload_file(
             let session_id_for_error = session_id.clone();
             tokio::spawn(async move {
                 if let Err(e) = process_video(
-                    input_file.path().to_path_buf(),
-                    output_path,
+                    Box::pin(ReceiverStream::new(rx)),
+                    output_id,
+                    state_clone.store,
                     state_clone.processing_status,
                     session_id_clone,
                 )
                 .await
                 {
                     eprintln!("Error processing video: {}", e);
-                    // Update the processing status with the error
-                    let mut status_map = status_clone.lock().unwrap();
-                    if let Some(status) = status_map.get_mut(&session_id_for_error) {
-                        status.status = "failed".to_string();
-                        status.error = Some(e.to_string());
+                    // `process_video` already publishes a specific status (e.g. `client_failed`
+                    // for a format it couldn't normalize) before returning this error on some
+                    // paths, so only fall back to a generic failure if nothing more specific is
+                    // already recorded.
+                    let already_reported = current_status(&status_clone, &session_id_for_error)
+                        .is_some_and(|s| s.status == "failed");
+                    if !already_reported {
+                        publish_status(
+                            &status_clone,
+                            &session_id_for_error,
+                            ProcessingStatus::failed(e.to_string()),
+                        );
                     }
                 }
             });
 
+            loop {
+                let chunk_result = match field.chunk().await {
+                    Ok(maybe_chunk) => maybe_chunk,
+                    Err(e) => {
+                        eprintln!("Error getting chunk: {}", e);
+                        http_error_bail!(INTERNAL_SERVER_ERROR, "Failed to process upload: {}", e)
+                    }
+                };
+
+                // If no more chunks, break the loop
+                let Some(chunk) = chunk_result else {
+                    break;
+                };
+
+                // The receiver only disappears if the background task already gave up (e.g. the
+                // Python backend request failed), in which case there is nothing left to forward.
+                if tx.send(Ok(chunk)).await.is_err() {
+                    break;
+                }
+            }
+
             return Ok(session_id);
         }
     }
     http_error_bail!(BAD_REQUEST, "No video file found");
 }
 
+#[derive(Debug, Deserialize)]
+struct IngestUrlRequest {
+    url: String,
+}
+
+/// How long [`ingest_url`] lets `yt-dlp` run before giving up. Overridable via
+/// `YT_DLP_TIMEOUT_SECS`.
+fn yt_dlp_timeout() -> std::time::Duration {
+    let secs = std::env::var("YT_DLP_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(600);
+    std::time::Duration::from_secs(secs)
+}
+
+/// Accepts a source video URL, downloads it with `yt-dlp`, and feeds it into the same
+/// watermarking/signing pipeline as `/upload`, returning a `session_id` to poll just like
+/// `/upload` does.
+#[axum::debug_handler]
+async fn ingest_url(
+    State(state): State<AppState>,
+    axum::Json(payload): axum::Json<IngestUrlRequest>,
+) -> HttpJsonResult<String> {
+    let session_id = Uuid::new_v4().to_string();
+    let output_id = format!("{}.mp4", session_id);
+    let download_path = std::path::Path::new("./data/temp").join(format!("{}-source.mp4", session_id));
+
+    start_status(
+        &state.processing_status,
+        &session_id,
+        ProcessingStatus::uploading(),
+    );
+
+    let yt_dlp_result = tokio::time::timeout(
+        yt_dlp_timeout(),
+        tokio::process::Command::new("yt-dlp")
+            .arg("--no-playlist")
+            .arg("-f")
+            .arg("mp4/bestvideo+bestaudio")
+            .arg("--merge-output-format")
+            .arg("mp4")
+            .arg("-o")
+            .arg(&download_path)
+            .arg(&payload.url)
+            .output(),
+    )
+    .await;
+
+    let output = match yt_dlp_result {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => {
+            let error_msg = format!("Failed to spawn yt-dlp: {}", e);
+            publish_status(
+                &state.processing_status,
+                &session_id,
+                ProcessingStatus::failed(error_msg.clone()),
+            );
+            http_error_bail!(INTERNAL_SERVER_ERROR, "{}", error_msg);
+        }
+        Err(_) => {
+            let error_msg = "yt-dlp timed out".to_string();
+            publish_status(
+                &state.processing_status,
+                &session_id,
+                ProcessingStatus::failed(error_msg.clone()),
+            );
+            http_error_bail!(INTERNAL_SERVER_ERROR, "{}", error_msg);
+        }
+    };
+
+    if !output.status.success() {
+        let error_msg = format!(
+            "yt-dlp failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        publish_status(
+            &state.processing_status,
+            &session_id,
+            ProcessingStatus::client_failed(error_msg.clone()),
+        );
+        http_error_bail!(BAD_REQUEST, "{}", error_msg);
+    }
+
+    publish_status(
+        &state.processing_status,
+        &session_id,
+        ProcessingStatus::processing(),
+    );
+
+    // Process the video in the background, same as /upload.
+    let state_clone = state.clone();
+    let session_id_clone = session_id.clone();
+    let status_clone = state.processing_status.clone();
+    let session_id_for_error = session_id.clone();
+    tokio::spawn(async move {
+        let input_stream: store::ByteStream = match tokio::fs::File::open(&download_path).await {
+            Ok(file) => Box::pin(ReaderStream::new(file)),
+            Err(e) => {
+                eprintln!("Error opening downloaded video: {}", e);
+                publish_status(
+                    &status_clone,
+                    &session_id_for_error,
+                    ProcessingStatus::failed(format!("Failed to open downloaded video: {}", e)),
+                );
+                return;
+            }
+        };
+
+        if let Err(e) = process_video(
+            input_stream,
+            output_id,
+            state_clone.store,
+            state_clone.processing_status,
+            session_id_clone,
+        )
+        .await
+        {
+            eprintln!("Error processing video: {}", e);
+            // `process_video` already publishes a specific status (e.g. `client_failed` for a
+            // format it couldn't normalize) before returning this error on some paths, so only
+            // fall back to a generic failure if nothing more specific is already recorded.
+            let already_reported = current_status(&status_clone, &session_id_for_error)
+                .is_some_and(|s| s.status == "failed");
+            if !already_reported {
+                publish_status(
+                    &status_clone,
+                    &session_id_for_error,
+                    ProcessingStatus::failed(e.to_string()),
+                );
+            }
+        }
+
+        let _ = tokio::fs::remove_file(&download_path).await;
+    });
+
+    Ok(session_id)
+}
+
 async fn get_status(
     State(state): State<AppState>,
     axum::extract::Path(session_id): axum::extract::Path<String>,
 ) -> impl IntoResponse {
-    if let Some(status) = state.processing_status.lock().unwrap().get(&session_id) {
-        axum::Json(status.clone())
+    match current_status(&state.processing_status, &session_id) {
+        Some(status) => axum::Json(status),
+        None => axum::Json(ProcessingStatus::not_found()),
+    }
+}
+
+/// Pushes `ProcessingStatus` transitions for `session_id` to the client as they happen, sending
+/// the current status immediately on connect and closing the socket once a terminal state
+/// (`completed`/`failed`) is reached. Closes immediately if the session isn't tracked.
+async fn ws_status(
+    State(state): State<AppState>,
+    axum::extract::Path(session_id): axum::extract::Path<String>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| push_status_updates(socket, state, session_id))
+}
+
+async fn push_status_updates(mut socket: WebSocket, state: AppState, session_id: String) {
+    let mut rx = {
+        let status_map = state.processing_status.lock().unwrap();
+        match status_map.get(&session_id) {
+            Some(tx) => tx.subscribe(),
+            None => return,
+        }
+    };
+
+    loop {
+        let status = rx.borrow().clone();
+        let is_terminal = status.status == "completed" || status.status == "failed";
+
+        let Ok(payload) = serde_json::to_string(&status) else {
+            break;
+        };
+        if socket.send(Message::Text(payload.into())).await.is_err() {
+            break;
+        }
+        if is_terminal {
+            break;
+        }
+        if rx.changed().await.is_err() {
+            break;
+        }
+    }
+}
+
+/// A single byte range resolved against the total size of a file, `start` and `end` inclusive.
+struct ByteRange {
+    start: u64,
+    end: u64,
+}
+
+/// Parses a `Range: bytes=...` header value against a known total length.
+///
+/// Supports a single range of the form `start-end`, `start-` (open-ended) and `-suffix_len`
+/// (suffix range). Multiple comma-separated ranges and anything else that does not resolve to a
+/// satisfiable range within `[0, total)` is rejected, mirroring the `416` behavior callers should
+/// surface for unsatisfiable ranges.
+fn parse_byte_range(range_header: &str, total: u64) -> Result<ByteRange, ()> {
+    let range = range_header.strip_prefix("bytes=").ok_or(())?;
+    if range.contains(',') {
+        return Err(()); // multiple ranges are not supported
+    }
+    let (start_str, end_str) = range.split_once('-').ok_or(())?;
+
+    if start_str.is_empty() {
+        // Suffix range: `bytes=-500` means the last 500 bytes.
+        let suffix_len: u64 = end_str.parse().map_err(|_| ())?;
+        if suffix_len == 0 || total == 0 {
+            return Err(());
+        }
+        let start = total.saturating_sub(suffix_len);
+        return Ok(ByteRange {
+            start,
+            end: total - 1,
+        });
+    }
+
+    let start: u64 = start_str.parse().map_err(|_| ())?;
+    let end = if end_str.is_empty() {
+        total.saturating_sub(1)
     } else {
-        axum::Json(ProcessingStatus {
-            status: "not_found".to_string(),
-            error: Some("Session not found".to_string()),
-        })
+        end_str.parse().map_err(|_| ())?
+    };
+
+    if total == 0 || start >= total || start > end {
+        return Err(());
+    }
+
+    Ok(ByteRange {
+        start,
+        end: end.min(total - 1),
+    })
+}
+
+/// Streams the object stored under `id` as an HTTP response, honoring an optional `Range` header.
+///
+/// With no `Range` header this streams the whole object as a normal `200` (never reading it fully
+/// into memory). With a satisfiable `Range` header it opens just the requested window and returns
+/// a `206 Partial Content` covering that slice. An unsatisfiable range yields `416`, and a missing
+/// object yields `404`.
+async fn stream_object_response(
+    store: &dyn Store,
+    id: &str,
+    content_type: &str,
+    range_header: Option<&str>,
+) -> Response {
+    // Opening with no range both probes the total length and, in the common case, gives us the
+    // reader we need, so a plain GET never pays for a second round-trip to the backing store.
+    let whole = match store.open_stream(id, None).await {
+        Ok(object) => object,
+        Err(_) => {
+            return Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(axum::body::Body::from("File not found"))
+                .unwrap()
+                .into_response()
+        }
+    };
+    let total = whole.total_len;
+
+    let range = match range_header {
+        Some(range_header) => match parse_byte_range(range_header, total) {
+            Ok(range) => Some(range),
+            Err(()) => {
+                return Response::builder()
+                    .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                    .header("content-range", format!("bytes */{}", total))
+                    .body(axum::body::Body::empty())
+                    .unwrap()
+                    .into_response()
+            }
+        },
+        None => None,
+    };
+
+    match range {
+        Some(ByteRange { start, end }) => {
+            let len = end - start + 1;
+            let ranged = match store.open_stream(id, Some((start, end))).await {
+                Ok(object) => object,
+                Err(e) => {
+                    return Response::builder()
+                        .status(StatusCode::INTERNAL_SERVER_ERROR)
+                        .body(axum::body::Body::from(format!(
+                            "Failed to open object: {}",
+                            e
+                        )))
+                        .unwrap()
+                        .into_response()
+                }
+            };
+            let stream = ReaderStream::new(ranged.reader);
+            Response::builder()
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header("content-type", content_type)
+                .header("accept-ranges", "bytes")
+                .header("content-range", format!("bytes {}-{}/{}", start, end, total))
+                .header("content-length", len.to_string())
+                .body(axum::body::Body::from_stream(stream))
+                .unwrap()
+                .into_response()
+        }
+        None => {
+            let stream = ReaderStream::new(whole.reader);
+            Response::builder()
+                .status(StatusCode::OK)
+                .header("content-type", content_type)
+                .header("accept-ranges", "bytes")
+                .header("content-length", total.to_string())
+                .body(axum::body::Body::from_stream(stream))
+                .unwrap()
+                .into_response()
+        }
     }
 }
 
 async fn download_file(
     State(state): State<AppState>,
     axum::extract::Path(session_id): axum::extract::Path<String>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
-    let status = state
-        .processing_status
-        .lock()
-        .unwrap()
-        .get(&session_id)
-        .cloned();
+    let status = current_status(&state.processing_status, &session_id);
 
     match status {
         Some(status) if status.status == "completed" => {
-            let file_path = PathBuf::from("data/processed").join(format!("{}.mp4", session_id));
-            if let Ok(file) = tokio_fs::read(&file_path).await {
-                Response::builder()
-                    .header("content-type", "video/mp4")
-                    .body(axum::body::Body::from(file))
-                    .unwrap()
-                    .into_response()
-            } else {
-                Response::builder()
-                    .status(404)
-                    .body(axum::body::Body::from("File not found"))
-                    .unwrap()
-                    .into_response()
-            }
+            let id = format!("{}.mp4", session_id);
+            let range_header = headers
+                .get(HeaderName::from_static("range"))
+                .and_then(|v| v.to_str().ok());
+            stream_object_response(state.store.as_ref(), &id, "video/mp4", range_header).await
         }
         Some(status) if status.status == "failed" => Response::builder()
-            .status(500)
+            .status(if status.client_error { 400 } else { 500 })
             .body(axum::body::Body::from(
                 status.error.unwrap_or_else(|| "Unknown error".to_string()),
             ))
@@ -430,28 +1209,37 @@ async fn download_file(
     }
 }
 
-// File operations for VIDEO_STORE
-async fn save_video_store() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+// File operations for VIDEO_STORE, routed through the configured `Store` (not a hardcoded local
+// path) so the index lives wherever the videos it maps to live.
+async fn save_video_store(
+    blob_store: &dyn Store,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let json = {
-        let store = VIDEO_STORE.lock().unwrap();
-        serde_json::to_string(&*store)?
+        let video_store = VIDEO_STORE.lock().unwrap();
+        serde_json::to_string(&*video_store)?
     };
-    tokio_fs::write("data/video_store.json", json).await?;
+    let stream: store::ByteStream =
+        Box::pin(stream::once(async move { Ok(Bytes::from(json.into_bytes())) }));
+    blob_store.save_stream(VIDEO_STORE_OBJECT_ID, stream).await?;
     Ok(())
 }
 
-async fn load_video_store() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    match tokio_fs::read("data/video_store.json").await {
-        Ok(contents) => {
-            let json = String::from_utf8(contents)?;
-            let loaded_store: HashMap<String, VideoData> = serde_json::from_str(&json)?;
-            let mut store = VIDEO_STORE.lock().unwrap();
-            *store = loaded_store;
-            Ok(())
-        }
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()), // File doesn't exist yet
-        Err(e) => Err(Box::new(e)),
-    }
+async fn load_video_store(
+    blob_store: &dyn Store,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Not finding an existing index is the expected first-run case, so any failure to open it
+    // (missing object, fresh bucket, ...) just starts from an empty store rather than failing
+    // startup.
+    let Ok(mut opened) = blob_store.open_stream(VIDEO_STORE_OBJECT_ID, None).await else {
+        return Ok(());
+    };
+    let mut contents = Vec::new();
+    opened.reader.read_to_end(&mut contents).await?;
+    let json = String::from_utf8(contents)?;
+    let loaded_store: HashMap<u64, VideoData> = serde_json::from_str(&json)?;
+    let mut video_store = VIDEO_STORE.lock().unwrap();
+    *video_store = loaded_store;
+    Ok(())
 }
 
 #[derive(Debug, Deserialize)]
@@ -459,19 +1247,19 @@ struct MessageBitsRequest {
     message_bits_hex: String,
 }
 
+/// Nearest-neighbor fallback for extracted bits that failed to decode as a valid payload (CRC
+/// mismatch, no watermark, or the source video isn't in the store). Rejects the closest match if
+/// its normalized Hamming distance to `target_bits` exceeds `max_distance`, so an unrecognized
+/// watermark reports no match instead of the closest-but-wrong video.
 fn find_closest_video(
-    store: &HashMap<String, VideoData>,
+    store: &HashMap<u64, VideoData>,
     target_bits: &[f32],
+    max_distance: f32,
 ) -> Option<VideoData> {
     store
         .values()
         .map(|video| {
-            let distance = video
-                .message_bits
-                .iter()
-                .zip(target_bits.iter())
-                .map(|(a, b)| (a - b).powi(2))
-                .sum::<f32>();
+            let distance = normalized_hamming_distance(&video.message_bits, target_bits);
             (video, distance)
         })
         .min_by(|(_, dist1), (_, dist2)| {
@@ -479,12 +1267,14 @@ fn find_closest_video(
                 .partial_cmp(dist2)
                 .unwrap_or(std::cmp::Ordering::Equal)
         })
+        .filter(|(_, distance)| *distance <= max_distance)
         .map(|(video, _)| video.clone())
 }
 
 #[axum::debug_handler]
 async fn analyze_file(
-    _state: State<AppState>,
+    State(state): State<AppState>,
+    headers: HeaderMap,
     mut multipart: Multipart,
 ) -> HttpJsonResult<impl IntoResponse> {
     let mut temp_file = None;
@@ -545,44 +1335,49 @@ async fn analyze_file(
                 }
             };
 
-            // Find the closest video in our store
+            // Try to decode an exact payload id out of the extracted bits first; only fall back
+            // to the nearest-neighbor scan if decoding fails (e.g. noise pushed it past what the
+            // repetition code and CRC can correct for) or the decoded id isn't in the store.
             let video_data = {
                 let store = VIDEO_STORE.lock().unwrap();
-                find_closest_video(&store, &python_response.extracted_bits)
+                decode_payload(&python_response.extracted_bits, repetition_factor())
+                    .and_then(|decoded| store.get(&decoded.id).cloned())
+                    .or_else(|| {
+                        find_closest_video(
+                            &store,
+                            &python_response.extracted_bits,
+                            max_watermark_hamming_distance(),
+                        )
+                    })
             };
 
             if let Some(video_data) = video_data {
                 // Clone video data before await
                 let video_data = video_data.clone();
 
-                // Open the video file
-                match tokio::fs::File::open(&video_data.path).await {
-                    Ok(file) => {
-                        let stream = ReaderStream::new(file);
-                        let body = axum::body::Body::from_stream(stream);
-
-                        let ret = Response::builder()
-                            .header(
-                                "content-type",
-                                mime_guess::from_path(&video_data.path)
-                                    .first_or_octet_stream()
-                                    .as_ref(),
-                            )
-                            .header(
-                                "content-disposition",
-                                format!(
-                                    "attachment; filename=\"{}\"",
-                                    video_data.path.split('/').last().unwrap_or("video.mp4")
-                                ),
-                            )
-                            .body(body)
-                            .map_status(StatusCode::INTERNAL_SERVER_ERROR)?;
-                        Ok(ret)
-                    }
-                    Err(e) => {
-                        http_error_bail!(INTERNAL_SERVER_ERROR, "Failed to open video file: {}", e)
-                    }
-                }
+                let content_type = mime_guess::from_path(&video_data.path)
+                    .first_or_octet_stream()
+                    .to_string();
+                let range_header = headers
+                    .get(HeaderName::from_static("range"))
+                    .and_then(|v| v.to_str().ok());
+                let mut response = stream_object_response(
+                    state.store.as_ref(),
+                    &video_data.path,
+                    &content_type,
+                    range_header,
+                )
+                .await;
+                response.headers_mut().insert(
+                    "content-disposition",
+                    format!(
+                        "attachment; filename=\"{}\"",
+                        video_data.path.split('/').last().unwrap_or("video.mp4")
+                    )
+                    .parse()
+                    .unwrap(),
+                );
+                Ok(response)
             } else {
                 http_error_bail!(NOT_FOUND, "No matching video found");
             }
@@ -598,6 +1393,157 @@ async fn analyze_file(
     }
 }
 
+/// Outcome of validating an uploaded video's embedded C2PA manifest.
+///
+/// Kept as three distinct variants (rather than a bare bool) so clients can tell an unsigned
+/// video apart from one whose signature or watermark assertion doesn't check out.
+///
+/// `Valid` means the embedded manifest is cryptographically self-consistent and unmodified (its
+/// `c2pa::Reader` validation status is clean), carries a `c2pa.watermark` assertion, and was
+/// signed by this service's own [`PUBLIC_KEY`] — a video signed by a *different* Ed25519 key (or
+/// a different C2PA-producing tool entirely) reports `Tampered` instead, even though its own
+/// embedded signature validates fine on its own terms.
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum VerifyResult {
+    Valid {
+        message_bits_hex: String,
+        message_bits_len: usize,
+        message_bits: Vec<f32>,
+        /// Repetition factor recorded in the assertion at signing time, so the payload below was
+        /// decoded without relying on this service's current configuration.
+        repetition_factor: usize,
+        /// The payload id recovered via majority vote and CRC check, if the recorded repetition
+        /// factor was enough to recover one.
+        payload: Option<DecodedPayload>,
+    },
+    Tampered {
+        reason: String,
+        validation_errors: Vec<String>,
+    },
+    Unsigned {
+        reason: String,
+    },
+}
+
+/// Reads back the C2PA manifest embedded in an uploaded video, validates that its embedded
+/// signature is cryptographically well-formed and untampered, and extracts the `c2pa.watermark`
+/// assertion written by [`create_c2pa_manifest`]. See [`VerifyResult::Valid`] for what "valid"
+/// does and doesn't guarantee about who signed it.
+#[axum::debug_handler]
+async fn verify_file(
+    _state: State<AppState>,
+    mut multipart: Multipart,
+) -> HttpJsonResult<axum::Json<VerifyResult>> {
+    let mut temp_file = None;
+    while let Some(field) = multipart.next_field().await.unwrap() {
+        if field.name().unwrap() == "video" {
+            let mut file = NamedTempFile::new_in("./data/temp").unwrap();
+            let body_with_io_error = field.map_err(|err| io::Error::new(io::ErrorKind::Other, err));
+            let body_reader = StreamReader::new(body_with_io_error);
+            futures::pin_mut!(body_reader);
+            tokio::io::copy(
+                &mut body_reader,
+                &mut AllowStdIo::new(file.as_file_mut()).compat_write(),
+            )
+            .await
+            .unwrap();
+            temp_file = Some(file);
+        }
+    }
+
+    let Some(mut file) = temp_file else {
+        http_error_bail!(BAD_REQUEST, "No video file provided");
+    };
+
+    file.as_file_mut()
+        .rewind()
+        .map_status(StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let reader = match c2pa::Reader::from_stream("video/mp4", file.as_file_mut()) {
+        Ok(reader) => reader,
+        Err(e) => {
+            return Ok(axum::Json(VerifyResult::Unsigned {
+                reason: format!("No C2PA manifest found: {}", e),
+            }));
+        }
+    };
+
+    // `Reader::validation_status` is documented as returning only failure codes (success and
+    // informational statuses live elsewhere on the reader), so any non-empty result here is a
+    // genuine validation failure and not just informational noise.
+    let validation_errors: Vec<String> = reader
+        .validation_status()
+        .map(|statuses| statuses.iter().map(|s| s.code().to_string()).collect())
+        .unwrap_or_default();
+
+    if !validation_errors.is_empty() {
+        return Ok(axum::Json(VerifyResult::Tampered {
+            reason: "C2PA signature validation failed".to_string(),
+            validation_errors,
+        }));
+    }
+
+    // `c2pa::Reader` only checks that the embedded signature is internally consistent — it has no
+    // notion of *this* deployment's key pair, so a video signed by a different Ed25519 key (or a
+    // different C2PA-producing tool) would otherwise also validate clean. Pin the recovered
+    // signer to `PUBLIC_KEY`, the same raw bytes `create_c2pa_manifest` hands `CallbackSigner` at
+    // signing time, so only videos this service itself watermarked come back `Valid`.
+    let signer_certs = reader
+        .active_manifest()
+        .and_then(|manifest| manifest.signature_info())
+        .map(|info| info.cert_chain.as_slice());
+    if signer_certs != Some(PUBLIC_KEY) {
+        return Ok(axum::Json(VerifyResult::Tampered {
+            reason: "Video's signing key does not match this service's key".to_string(),
+            validation_errors: vec![],
+        }));
+    }
+
+    let assertion: Option<serde_json::Value> = reader
+        .active_manifest()
+        .and_then(|manifest| manifest.get_assertion("c2pa.watermark"));
+
+    let Some(assertion) = assertion else {
+        return Ok(axum::Json(VerifyResult::Tampered {
+            reason: "Video is signed but missing the c2pa.watermark assertion".to_string(),
+            validation_errors: vec![],
+        }));
+    };
+
+    let Some(message_bits_hex) = assertion
+        .get("message_bits_hex")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+    else {
+        return Ok(axum::Json(VerifyResult::Tampered {
+            reason: "c2pa.watermark assertion is missing message_bits_hex".to_string(),
+            validation_errors: vec![],
+        }));
+    };
+    let message_bits_len = assertion
+        .get("message_bits_len")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as usize;
+    let repetition_factor = assertion
+        .get("repetition_factor")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as usize;
+
+    let mut message_bits = message_bits_from_hex(&message_bits_hex);
+    message_bits.truncate(message_bits_len);
+
+    let payload = decode_payload(&message_bits, repetition_factor);
+
+    Ok(axum::Json(VerifyResult::Valid {
+        message_bits_hex,
+        message_bits_len,
+        message_bits,
+        repetition_factor,
+        payload,
+    }))
+}
+
 #[tokio::main]
 async fn main() {
     tokio_fs::create_dir_all("./data/processed").await.unwrap();
@@ -610,19 +1556,27 @@ async fn main() {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    // Load video store at startup
-    if let Err(e) = load_video_store().await {
+    let object_store = store::from_env()
+        .await
+        .expect("failed to initialize object store");
+
+    // Load video store at startup, from the same backend the signed videos themselves live in.
+    if let Err(e) = load_video_store(object_store.as_ref()).await {
         eprintln!("Failed to load video store: {}", e);
     }
 
     let state = AppState {
         processing_status: Arc::new(Mutex::new(HashMap::new())),
+        store: object_store,
     };
 
     let app = Router::new()
         .route("/upload", post(upload_file))
+        .route("/ingest-url", post(ingest_url))
         .route("/analyze", post(analyze_file))
+        .route("/verify", post(verify_file))
         .route("/status/{session_id}", get(get_status))
+        .route("/ws/status/{session_id}", get(ws_status))
         .route("/download/{session_id}", get(download_file))
         .layer(CorsLayer::permissive())
         .layer(DefaultBodyLimit::max(250 * 1024 * 1024 * 1024))
@@ -0,0 +1,198 @@
+//! Storage backend for processed videos, pluggable between the local filesystem and an
+//! S3-compatible object store (MinIO, Garage, AWS S3, ...) selected at startup.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::{Stream, TryStreamExt};
+use std::{
+    io,
+    path::PathBuf,
+    pin::Pin,
+    sync::Arc,
+};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+/// A chunked byte stream accepted by [`Store::save_stream`].
+pub type ByteStream = Pin<Box<dyn Stream<Item = io::Result<Bytes>> + Send>>;
+
+/// A handle to an opened object: its total size and a reader positioned at the start of the
+/// requested range (or the start of the object, if no range was requested).
+pub struct OpenedObject {
+    pub total_len: u64,
+    pub reader: Pin<Box<dyn AsyncRead + Send>>,
+}
+
+/// Storage backend for processed videos and `video_store.json`.
+///
+/// Implementations are looked up by opaque identifier, not by filesystem path, so the same
+/// pipeline code works whether objects live on local disk or in a remote object store.
+#[async_trait]
+pub trait Store: Send + Sync {
+    /// Persists `stream` under `id`, overwriting any existing object.
+    async fn save_stream(&self, id: &str, stream: ByteStream) -> Result<()>;
+
+    /// Opens the object stored under `id`. `range` is an inclusive `(start, end)` byte range;
+    /// `None` opens the whole object from the start.
+    async fn open_stream(&self, id: &str, range: Option<(u64, u64)>) -> Result<OpenedObject>;
+
+    /// Removes the object stored under `id`. Not finding it is not an error.
+    async fn remove(&self, id: &str) -> Result<()>;
+}
+
+/// Filesystem-backed [`Store`], rooted at a single directory.
+pub struct FsStore {
+    root: PathBuf,
+}
+
+impl FsStore {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    fn path_for(&self, id: &str) -> PathBuf {
+        self.root.join(id)
+    }
+}
+
+#[async_trait]
+impl Store for FsStore {
+    async fn save_stream(&self, id: &str, mut stream: ByteStream) -> Result<()> {
+        tokio::fs::create_dir_all(&self.root).await?;
+        let mut file = tokio::fs::File::create(self.path_for(id)).await?;
+        while let Some(chunk) = stream.try_next().await? {
+            file.write_all(&chunk).await?;
+        }
+        file.flush().await?;
+        Ok(())
+    }
+
+    async fn open_stream(&self, id: &str, range: Option<(u64, u64)>) -> Result<OpenedObject> {
+        let path = self.path_for(id);
+        let total_len = tokio::fs::metadata(&path)
+            .await
+            .with_context(|| format!("object not found: {}", id))?
+            .len();
+        let mut file = tokio::fs::File::open(&path).await?;
+
+        let reader: Pin<Box<dyn AsyncRead + Send>> = match range {
+            Some((start, end)) => {
+                file.seek(io::SeekFrom::Start(start)).await?;
+                Box::pin(file.take(end - start + 1))
+            }
+            None => Box::pin(file),
+        };
+
+        Ok(OpenedObject { total_len, reader })
+    }
+
+    async fn remove(&self, id: &str) -> Result<()> {
+        match tokio::fs::remove_file(self.path_for(id)).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// S3-compatible object store, configured from `S3_*` environment variables so the same code
+/// works against AWS S3, MinIO, Garage, or any other S3-compatible endpoint.
+pub struct S3Store {
+    client: aws_sdk_s3::Client,
+    bucket: String,
+}
+
+impl S3Store {
+    /// Builds an [`S3Store`] from the environment.
+    ///
+    /// `S3_BUCKET` is required. `S3_ENDPOINT` selects a non-AWS endpoint (MinIO/Garage) and
+    /// implies path-style addressing; `S3_REGION` defaults to `us-east-1`. Credentials are
+    /// resolved through the standard AWS provider chain (env vars, shared config, IMDS, ...).
+    pub async fn from_env() -> Result<Self> {
+        let bucket = std::env::var("S3_BUCKET").context("S3_BUCKET is not set")?;
+        let region = std::env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string());
+
+        let mut loader = aws_config::from_env().region(aws_sdk_s3::config::Region::new(region));
+        if let Ok(endpoint) = std::env::var("S3_ENDPOINT") {
+            loader = loader.endpoint_url(endpoint);
+        }
+        let shared_config = loader.load().await;
+
+        let s3_config = aws_sdk_s3::config::Builder::from(&shared_config)
+            .force_path_style(std::env::var("S3_ENDPOINT").is_ok())
+            .build();
+
+        Ok(Self {
+            client: aws_sdk_s3::Client::from_conf(s3_config),
+            bucket,
+        })
+    }
+}
+
+#[async_trait]
+impl Store for S3Store {
+    async fn save_stream(&self, id: &str, mut stream: ByteStream) -> Result<()> {
+        let mut buf = Vec::new();
+        while let Some(chunk) = stream.try_next().await? {
+            buf.extend_from_slice(&chunk);
+        }
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(id)
+            .body(buf.into())
+            .send()
+            .await
+            .with_context(|| format!("failed to upload object: {}", id))?;
+        Ok(())
+    }
+
+    async fn open_stream(&self, id: &str, range: Option<(u64, u64)>) -> Result<OpenedObject> {
+        let mut request = self.client.get_object().bucket(&self.bucket).key(id);
+        if let Some((start, end)) = range {
+            request = request.range(format!("bytes={}-{}", start, end));
+        }
+        let output = request
+            .send()
+            .await
+            .with_context(|| format!("object not found: {}", id))?;
+
+        let total_len = match (range, output.content_range()) {
+            // `Content-Range: bytes start-end/total` on a ranged response.
+            (Some(_), Some(content_range)) => content_range
+                .rsplit('/')
+                .next()
+                .and_then(|total| total.parse().ok())
+                .unwrap_or(output.content_length().unwrap_or(0) as u64),
+            _ => output.content_length().unwrap_or(0) as u64,
+        };
+
+        let reader = output.body.into_async_read();
+        Ok(OpenedObject {
+            total_len,
+            reader: Box::pin(reader),
+        })
+    }
+
+    async fn remove(&self, id: &str) -> Result<()> {
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(id)
+            .send()
+            .await
+            .with_context(|| format!("failed to delete object: {}", id))?;
+        Ok(())
+    }
+}
+
+/// Builds the configured [`Store`] from the environment.
+///
+/// `STORE_BACKEND=s3` selects [`S3Store`]; anything else (including unset) falls back to
+/// [`FsStore`] rooted at `./data/processed`, matching the service's historical on-disk layout.
+pub async fn from_env() -> Result<Arc<dyn Store>> {
+    match std::env::var("STORE_BACKEND").as_deref() {
+        Ok("s3") => Ok(Arc::new(S3Store::from_env().await?)),
+        _ => Ok(Arc::new(FsStore::new(PathBuf::from("./data/processed")))),
+    }
+}